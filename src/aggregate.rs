@@ -0,0 +1,135 @@
+//! Consensus pricing across every source, instead of trusting the first
+//! one that answers.
+
+/// Default percentage a price point may deviate from the median before
+/// it's treated as an outlier and dropped from the consensus.
+pub const DEFAULT_OUTLIER_THRESHOLD_PCT: f64 = 5.0;
+
+/// Result of reconciling one coin/currency's prices across sources.
+#[derive(Debug, Clone)]
+pub struct Consensus {
+    /// Median of the prices that survived outlier filtering
+    pub median: f64,
+    /// Highest minus lowest surviving price
+    pub spread: f64,
+    /// (source name, price) for every source that agreed with the consensus
+    pub agreeing: Vec<(String, f64)>,
+    /// (source name, price) for every source dropped as an outlier
+    pub outliers: Vec<(String, f64)>,
+}
+
+fn median(values: &[f64]) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    } else {
+        sorted[mid]
+    }
+}
+
+/// Reconcile a set of (source, price) readings into a consensus price,
+/// discarding any reading more than `threshold_pct` percent away from the
+/// median of all readings.
+pub fn reconcile(readings: &[(String, f64)], threshold_pct: f64) -> Consensus {
+    let all_prices: Vec<f64> = readings.iter().map(|(_, p)| *p).collect();
+    let initial_median = median(&all_prices);
+
+    let mut agreeing = Vec::new();
+    let mut outliers = Vec::new();
+
+    for (source, price) in readings {
+        let deviation_pct = if initial_median == 0.0 {
+            0.0
+        } else {
+            ((price - initial_median) / initial_median).abs() * 100.0
+        };
+
+        if deviation_pct > threshold_pct {
+            outliers.push((source.clone(), *price));
+        } else {
+            agreeing.push((source.clone(), *price));
+        }
+    }
+
+    // Every reading can end up more than `threshold_pct` from the median of
+    // a small/bimodal set (e.g. two sources straddling it). Rather than
+    // reconcile to nothing, fall back to treating every reading as agreeing
+    // so there's always at least one surviving price.
+    if agreeing.is_empty() {
+        agreeing = readings.to_vec();
+        outliers.clear();
+    }
+
+    let surviving_prices: Vec<f64> = agreeing.iter().map(|(_, p)| *p).collect();
+    let final_median = median(&surviving_prices);
+    let spread = surviving_prices
+        .iter()
+        .cloned()
+        .fold(f64::MIN, f64::max)
+        - surviving_prices.iter().cloned().fold(f64::MAX, f64::min);
+
+    Consensus {
+        median: final_median,
+        spread,
+        agreeing,
+        outliers,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_median_odd_count() {
+        assert_eq!(median(&[1.0, 3.0, 2.0]), 2.0);
+    }
+
+    #[test]
+    fn test_median_even_count() {
+        assert_eq!(median(&[1.0, 2.0, 3.0, 4.0]), 2.5);
+    }
+
+    #[test]
+    fn test_reconcile_drops_outlier() {
+        let readings = vec![
+            ("CoinGecko".to_string(), 100.0),
+            ("CoinCap".to_string(), 101.0),
+            ("Binance".to_string(), 99.0),
+            ("StaleFeed".to_string(), 200.0),
+        ];
+        let consensus = reconcile(&readings, 10.0);
+        assert_eq!(consensus.agreeing.len(), 3);
+        assert_eq!(consensus.outliers, vec![("StaleFeed".to_string(), 200.0)]);
+        assert_eq!(consensus.median, 100.0);
+    }
+
+    #[test]
+    fn test_reconcile_falls_back_when_every_reading_is_an_outlier() {
+        // Two readings straddling the median by more than the threshold:
+        // both get flagged as outliers against each other, which must not
+        // leave `agreeing` empty (and therefore must not panic on an empty
+        // median).
+        let readings = vec![
+            ("CoinGecko".to_string(), 100.0),
+            ("StaleFeed".to_string(), 120.0),
+        ];
+        let consensus = reconcile(&readings, 5.0);
+        assert_eq!(consensus.outliers.len(), 0);
+        assert_eq!(consensus.agreeing.len(), 2);
+        assert_eq!(consensus.median, 110.0);
+    }
+
+    #[test]
+    fn test_reconcile_all_agree() {
+        let readings = vec![
+            ("CoinGecko".to_string(), 100.0),
+            ("CoinCap".to_string(), 100.5),
+        ];
+        let consensus = reconcile(&readings, 5.0);
+        assert_eq!(consensus.outliers.len(), 0);
+        assert_eq!(consensus.agreeing.len(), 2);
+    }
+}
@@ -0,0 +1,126 @@
+//! Short-lived on-disk cache for provider responses, keyed by
+//! (coin, currency, source), so repeated invocations within the TTL window
+//! don't hammer rate-limited APIs.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Default time a cached price stays valid before it's treated as stale.
+pub const DEFAULT_TTL_SECS: u64 = 60;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct CacheEntry {
+    coin: String,
+    currency: String,
+    source: String,
+    price: f64,
+    fetched_at: u64,
+}
+
+pub struct Cache {
+    path: PathBuf,
+    ttl: Duration,
+    entries: Vec<CacheEntry>,
+}
+
+fn now_unix_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn default_cache_path() -> PathBuf {
+    std::env::temp_dir().join("web3-cli-price-cache.json")
+}
+
+impl Cache {
+    /// Load the cache from disk, or start empty if it doesn't exist / is
+    /// corrupt. A corrupt cache file is treated the same as a cold cache
+    /// rather than a hard error - it'll simply be rewritten.
+    pub fn load(ttl: Duration) -> Self {
+        let path = default_cache_path();
+        let entries = fs::read_to_string(&path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default();
+
+        Cache { path, ttl, entries }
+    }
+
+    /// A cache that never reads or writes anything, for `--no-cache`.
+    pub fn disabled() -> Self {
+        Cache {
+            path: default_cache_path(),
+            ttl: Duration::ZERO,
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn get(&self, coin: &str, currency: &str, source: &str) -> Option<f64> {
+        let now = now_unix_secs();
+        self.entries
+            .iter()
+            .find(|e| e.coin == coin && e.currency == currency && e.source == source)
+            .filter(|e| now.saturating_sub(e.fetched_at) < self.ttl.as_secs())
+            .map(|e| e.price)
+    }
+
+    pub fn put(&mut self, coin: &str, currency: &str, source: &str, price: f64) {
+        if self.ttl.is_zero() {
+            return;
+        }
+
+        let fetched_at = now_unix_secs();
+        if let Some(existing) = self
+            .entries
+            .iter_mut()
+            .find(|e| e.coin == coin && e.currency == currency && e.source == source)
+        {
+            existing.price = price;
+            existing.fetched_at = fetched_at;
+        } else {
+            self.entries.push(CacheEntry {
+                coin: coin.to_string(),
+                currency: currency.to_string(),
+                source: source.to_string(),
+                price,
+                fetched_at,
+            });
+        }
+
+        self.save();
+    }
+
+    fn save(&self) {
+        if let Ok(json) = serde_json::to_string(&self.entries) {
+            let _ = fs::write(&self.path, json);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_put_then_get_within_ttl() {
+        let mut cache = Cache {
+            path: std::env::temp_dir().join("web3-cli-test-cache.json"),
+            ttl: Duration::from_secs(60),
+            entries: Vec::new(),
+        };
+        cache.put("solana", "usd", "CoinGecko", 150.0);
+        assert_eq!(cache.get("solana", "usd", "CoinGecko"), Some(150.0));
+        assert_eq!(cache.get("solana", "eur", "CoinGecko"), None);
+    }
+
+    #[test]
+    fn test_disabled_cache_never_returns_a_hit() {
+        let mut cache = Cache::disabled();
+        cache.put("solana", "usd", "CoinGecko", 150.0);
+        assert_eq!(cache.get("solana", "usd", "CoinGecko"), None);
+    }
+}
@@ -1,164 +1,203 @@
-use reqwest;
-use serde::Deserialize;
+mod aggregate;
+mod cache;
+mod retry;
+mod server;
+mod sources;
+mod stream;
+
+use cache::Cache;
+use clap::{Parser, Subcommand};
+use sources::PriceQuery;
 use std::collections::HashMap;
-use std::env;
-
-#[derive(Deserialize, Debug)]
-struct CoinGeckoPrices {
-    solana: HashMap<String, f64>,
-}
-
-#[derive(Deserialize, Debug)]
-struct CoinCapResponse {
-    data: CoinCapAsset,
+use std::time::Duration;
+
+/// web3-cli: a multi-source crypto price checker
+#[derive(Parser, Debug)]
+#[command(name = "web3-cli", about = "Multi-source crypto/fiat price CLI")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
 }
 
-#[derive(Deserialize, Debug)]
-#[serde(rename_all = "camelCase")]
-struct CoinCapAsset {
-    price_usd: String,
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Fetch the current price for one or more coins
+    Price {
+        /// Comma-separated CoinGecko coin ids, e.g. bitcoin,ethereum,solana
+        #[arg(long, default_value = "solana")]
+        coins: String,
+        /// Comma-separated currencies to quote against, e.g. usd,eur
+        #[arg(long, default_value = "usd")]
+        vs: String,
+        /// Query every source and report a median consensus price instead
+        /// of stopping at the first source that answers
+        #[arg(long)]
+        aggregate: bool,
+        /// Percentage a price may deviate from the median before it's
+        /// dropped as an outlier (only used with --aggregate)
+        #[arg(long, default_value_t = aggregate::DEFAULT_OUTLIER_THRESHOLD_PCT)]
+        outlier_threshold: f64,
+        /// Don't read or write the on-disk price cache
+        #[arg(long)]
+        no_cache: bool,
+        /// How long a cached price stays valid, in seconds
+        #[arg(long, default_value_t = cache::DEFAULT_TTL_SECS)]
+        ttl: u64,
+    },
+    /// Run a long-lived JSON-RPC daemon serving price queries
+    Serve {
+        /// Address to bind the JSON-RPC HTTP server to
+        #[arg(long, default_value = "127.0.0.1:8787")]
+        addr: String,
+        /// How long a cached price stays valid, in seconds
+        #[arg(long, default_value_t = cache::DEFAULT_TTL_SECS)]
+        ttl: u64,
+    },
+    /// Stream live price updates from Binance's WebSocket ticker feed
+    Watch {
+        /// Comma-separated coin ids to watch, e.g. bitcoin,ethereum,solana
+        #[arg(long, default_value = "solana")]
+        coins: String,
+        /// Minimum seconds between printed updates for a given coin
+        #[arg(long, default_value_t = 1)]
+        interval: u64,
+    },
 }
 
-#[derive(Deserialize, Debug)]
-struct BinancePrice {
-    price: String,
-}
-
-/// Fetch SOL price from CoinGecko API
-fn fetch_from_coingecko(client: &reqwest::blocking::Client) -> Result<f64, Box<dyn std::error::Error>> {
-    let mut url = "https://api.coingecko.com/api/v3/simple/price?ids=solana&vs_currencies=usd".to_string();
-
-    // Support API key if provided
-    if let Ok(api_key) = env::var("COINGECKO_API_KEY") {
-        url = format!("{}&x_cg_demo_api_key={}", url, api_key);
+fn make_cache(no_cache: bool, ttl: u64) -> Cache {
+    if no_cache {
+        Cache::disabled()
+    } else {
+        Cache::load(Duration::from_secs(ttl))
     }
-
-    let resp = client.get(&url).send()?;
-    let status = resp.status();
-    let text = resp.text()?;
-
-    if !status.is_success() {
-        return Err(format!("CoinGecko API error: {}", status).into());
-    }
-
-    let data: CoinGeckoPrices = serde_json::from_str(&text)?;
-    let price = *data.solana.get("usd").ok_or("Price not found")?;
-    Ok(price)
 }
 
-/// Fetch SOL price from CoinCap API
-fn fetch_from_coincap(client: &reqwest::blocking::Client) -> Result<f64, Box<dyn std::error::Error>> {
-    let url = "https://api.coincap.io/v2/assets/solana";
-    let resp = client.get(url).send()?;
-    let status = resp.status();
-    let text = resp.text()?;
+fn run_price(coins: &str, vs: &str, no_cache: bool, ttl: u64) -> Result<(), Box<dyn std::error::Error>> {
+    let query = PriceQuery::new(coins, vs);
+    let mut cache = make_cache(no_cache, ttl);
 
-    if !status.is_success() {
-        return Err(format!("CoinCap API error: {}", status).into());
-    }
-
-    let data: CoinCapResponse = serde_json::from_str(&text)?;
-    let price = data.data.price_usd.parse::<f64>()?;
-    Ok(price)
-}
-
-/// Fetch SOL price from Binance API
-fn fetch_from_binance(client: &reqwest::blocking::Client) -> Result<f64, Box<dyn std::error::Error>> {
-    let url = "https://api.binance.com/api/v3/ticker/price?symbol=SOLUSDT";
-    let resp = client.get(url).send()?;
-    let status = resp.status();
-    let text = resp.text()?;
-
-    if !status.is_success() {
-        return Err(format!("Binance API error: {}", status).into());
-    }
-
-    let data: BinancePrice = serde_json::from_str(&text)?;
-    let price = data.price.parse::<f64>()?;
-    Ok(price)
-}
-
-/// Try fetching price from multiple sources with fallback
-fn fetch_sol_price() -> Result<f64, Box<dyn std::error::Error>> {
-    let client = reqwest::blocking::Client::builder()
-        .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36")
-        .timeout(std::time::Duration::from_secs(10))
-        .build()?;
-
-    // Try CoinGecko first
-    println!("🔗 Trying CoinGecko API...");
-    match fetch_from_coingecko(&client) {
-        Ok(price) => {
-            println!("✅ CoinGecko: Success");
-            return Ok(price);
+    match sources::fetch_prices(&query, &mut cache) {
+        Ok((prices, _source)) => {
+            println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+            for coin in &query.ids {
+                let Some(by_currency) = prices.get(coin) else {
+                    eprintln!("⚠️  No price returned for {}", coin);
+                    continue;
+                };
+                for currency in &query.vs_currencies {
+                    match by_currency.get(currency) {
+                        Some(price) => println!("💰 {} price: {:.2} {}", coin, price, currency.to_uppercase()),
+                        None => eprintln!("⚠️  No {} price for {}", currency, coin),
+                    }
+                }
+            }
+            println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+            Ok(())
         }
         Err(e) => {
-            eprintln!("⚠️  CoinGecko failed: {}", e);
+            eprintln!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+            eprintln!("{}", e);
+            eprintln!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+            eprintln!("\n💡 Tip: Set COINGECKO_API_KEY environment variable if you have one");
+            Err(e)
         }
     }
+}
 
-    // Fallback to CoinCap
-    println!("🔗 Trying CoinCap API...");
-    match fetch_from_coincap(&client) {
-        Ok(price) => {
-            println!("✅ CoinCap: Success");
-            return Ok(price);
-        }
-        Err(e) => {
-            eprintln!("⚠️  CoinCap failed: {}", e);
+fn run_price_aggregate(
+    coins: &str,
+    vs: &str,
+    outlier_threshold: f64,
+    no_cache: bool,
+    ttl: u64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let query = PriceQuery::new(coins, vs);
+    let mut cache = make_cache(no_cache, ttl);
+    let per_source = sources::fetch_from_all_sources(&query, &mut cache)?;
+
+    // Regroup the per-source price maps into per (coin, currency) readings
+    let mut readings: HashMap<(String, String), Vec<(String, f64)>> = HashMap::new();
+    for (source, result) in &per_source {
+        let Ok(prices) = result else {
+            if let Err(e) = result {
+                eprintln!("⚠️  {} failed: {}", source, e);
+            }
+            continue;
+        };
+        for coin in &query.ids {
+            let Some(by_currency) = prices.get(coin) else {
+                continue;
+            };
+            for currency in &query.vs_currencies {
+                if let Some(price) = by_currency.get(currency) {
+                    readings
+                        .entry((coin.clone(), currency.clone()))
+                        .or_default()
+                        .push((source.to_string(), *price));
+                }
+            }
         }
     }
 
-    // Fallback to Binance
-    println!("🔗 Trying Binance API...");
-    match fetch_from_binance(&client) {
-        Ok(price) => {
-            println!("✅ Binance: Success");
-            return Ok(price);
-        }
-        Err(e) => {
-            eprintln!("⚠️  Binance failed: {}", e);
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    for coin in &query.ids {
+        for currency in &query.vs_currencies {
+            let Some(points) = readings.get(&(coin.clone(), currency.clone())) else {
+                eprintln!("⚠️  No source returned a {} price for {}", currency, coin);
+                continue;
+            };
+
+            let consensus = aggregate::reconcile(points, outlier_threshold);
+            println!(
+                "💰 {} consensus: {:.2} {} (spread {:.4}, {} of {} sources agreed)",
+                coin,
+                consensus.median,
+                currency.to_uppercase(),
+                consensus.spread,
+                consensus.agreeing.len(),
+                points.len()
+            );
+            for (source, price) in &consensus.agreeing {
+                println!("   ✅ {}: {:.2}", source, price);
+            }
+            for (source, price) in &consensus.outliers {
+                println!("   ⚠️  {}: {:.2} (discarded as outlier)", source, price);
+            }
         }
     }
-
-    Err("❌ All API sources failed. Please check your internet connection or try again later.".into())
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    Ok(())
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    println!("🦀 Web3 Rust CLI - Solana Price Checker");
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-
-    match fetch_sol_price() {
-        Ok(price) => {
-            println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-            println!("💰 SOL price: ${:.2}", price);
-            println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-            Ok(())
-        }
-        Err(e) => {
-            eprintln!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-            eprintln!("{}", e);
-            eprintln!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-            eprintln!("\n💡 Tip: Set COINGECKO_API_KEY environment variable if you have one");
-            Err(e)
+    println!("🦀 Web3 Rust CLI - Crypto Price Checker");
+
+    let cli = Cli::parse();
+    match cli.command {
+        Command::Price { coins, vs, aggregate, outlier_threshold, no_cache, ttl } => {
+            if aggregate {
+                run_price_aggregate(&coins, &vs, outlier_threshold, no_cache, ttl)
+            } else {
+                run_price(&coins, &vs, no_cache, ttl)
+            }
         }
+        Command::Serve { addr, ttl } => server::run(&addr, ttl),
+        Command::Watch { coins, interval } => stream::run(&coins, Duration::from_secs(interval)),
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-
     #[test]
     fn test_price_display_format() {
         let price = 123.456789;
-        let formatted = format!("${:.2}", price);
-        assert_eq!(formatted, "$123.46");
+        let formatted = format!("{:.2}", price);
+        assert_eq!(formatted, "123.46");
     }
 
     #[test]
     fn test_environment_variable_handling() {
         // Test that env var access doesn't panic
-        let _ = env::var("COINGECKO_API_KEY");
+        let _ = std::env::var("COINGECKO_API_KEY");
     }
 }
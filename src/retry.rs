@@ -0,0 +1,114 @@
+//! Retry wrapper for `fetch_from_*` calls: backs off on rate limiting or
+//! server errors instead of immediately falling through to the next source.
+
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+/// Maximum number of attempts made against a single source before giving up
+/// and moving on to the next one in the fallback chain.
+pub const MAX_ATTEMPTS: u32 = 3;
+
+/// Error carrying enough HTTP context (status, `Retry-After`) for the retry
+/// wrapper to decide whether and how long to wait before trying again.
+#[derive(Debug)]
+pub struct FetchError {
+    pub message: String,
+    pub status: Option<reqwest::StatusCode>,
+    pub retry_after: Option<Duration>,
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl Error for FetchError {}
+
+fn is_retryable(status: reqwest::StatusCode) -> bool {
+    status.as_u16() == 429 || status.is_server_error()
+}
+
+/// Small deterministic-ish jitter so concurrent retries against the same
+/// source don't all wake up on the same tick. Avoids pulling in `rand` for
+/// a single call site.
+fn jitter(base: Duration) -> Duration {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    let jitter_ms = (nanos % 250) as u64;
+    base + Duration::from_millis(jitter_ms)
+}
+
+/// Call `attempt` up to `MAX_ATTEMPTS` times, backing off exponentially
+/// (1s, 2s, 4s, ... plus jitter) between tries. If the error is a
+/// [`FetchError`] carrying a `Retry-After`, that value is honored instead of
+/// the computed backoff. Non-retryable errors (anything that isn't a 429 or
+/// 5xx) return immediately.
+pub fn with_retry<T>(
+    source_name: &str,
+    mut attempt: impl FnMut() -> Result<T, Box<dyn Error>>,
+) -> Result<T, Box<dyn Error>> {
+    let mut backoff = Duration::from_secs(1);
+
+    for attempt_num in 1..=MAX_ATTEMPTS {
+        match attempt() {
+            Ok(value) => return Ok(value),
+            Err(e) => {
+                let fetch_error = e.downcast_ref::<FetchError>();
+                let retryable = fetch_error
+                    .and_then(|fe| fe.status)
+                    .map(is_retryable)
+                    .unwrap_or(false);
+
+                if !retryable || attempt_num == MAX_ATTEMPTS {
+                    return Err(e);
+                }
+
+                let wait = fetch_error
+                    .and_then(|fe| fe.retry_after)
+                    .unwrap_or_else(|| jitter(backoff));
+                eprintln!(
+                    "⚠️  {} rate-limited or unavailable, retrying in {:.1}s (attempt {}/{})",
+                    source_name,
+                    wait.as_secs_f64(),
+                    attempt_num,
+                    MAX_ATTEMPTS
+                );
+                std::thread::sleep(wait);
+                backoff *= 2;
+            }
+        }
+    }
+
+    unreachable!("loop always returns on the final attempt")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_retryable_status() {
+        assert!(is_retryable(reqwest::StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_retryable(reqwest::StatusCode::BAD_GATEWAY));
+        assert!(!is_retryable(reqwest::StatusCode::NOT_FOUND));
+    }
+
+    #[test]
+    fn test_with_retry_returns_immediately_on_non_retryable_error() {
+        let mut calls = 0;
+        let result: Result<(), Box<dyn Error>> = with_retry("test", || {
+            calls += 1;
+            Err(Box::new(FetchError {
+                message: "not found".into(),
+                status: Some(reqwest::StatusCode::NOT_FOUND),
+                retry_after: None,
+            }))
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+}
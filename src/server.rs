@@ -0,0 +1,247 @@
+//! `serve`: a long-running JSON-RPC-over-HTTP daemon so other local tools
+//! can query prices without paying a fresh HTTP handshake (and cold cache)
+//! on every invocation.
+//!
+//! Only one method is exposed today: `get_price { coin, vs_currency }`,
+//! which mirrors the `price` subcommand's fetch/fallback logic and shares
+//! its on-disk TTL cache across every caller.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Mutex;
+use std::time::Duration;
+use tiny_http::{Header, Response, Server};
+
+use crate::cache::Cache;
+use crate::sources::{self, PriceQuery};
+
+#[derive(Deserialize)]
+struct RpcRequest {
+    method: String,
+    #[serde(default)]
+    params: Value,
+    #[serde(default)]
+    id: Value,
+}
+
+#[derive(Serialize)]
+struct RpcResponse {
+    jsonrpc: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<RpcError>,
+    id: Value,
+}
+
+#[derive(Serialize)]
+struct RpcError {
+    code: i32,
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct GetPriceParams {
+    coin: String,
+    vs_currency: String,
+}
+
+/// Start the daemon and serve requests forever.
+pub fn run(addr: &str, ttl_secs: u64) -> Result<(), Box<dyn std::error::Error>> {
+    let server = Server::http(addr).map_err(|e| format!("failed to bind {}: {}", addr, e))?;
+    println!("🛰️  web3-cli daemon listening on http://{}", addr);
+    let cache = Mutex::new(Cache::load(Duration::from_secs(ttl_secs)));
+
+    loop {
+        serve_one(&server, &cache)?;
+    }
+}
+
+/// Block for exactly one request and answer it. Split out from [`run`] so
+/// tests can exercise the RPC handling over a real ephemeral-port socket
+/// without looping forever.
+pub fn serve_one(server: &Server, cache: &Mutex<Cache>) -> Result<(), Box<dyn std::error::Error>> {
+    let request = server.recv()?;
+    handle_connection(request, cache);
+    Ok(())
+}
+
+fn handle_connection(mut request: tiny_http::Request, cache: &Mutex<Cache>) {
+    let mut body = String::new();
+    if let Err(e) = request.as_reader().read_to_string(&mut body) {
+        eprintln!("⚠️  failed to read request body: {}", e);
+        return;
+    }
+
+    let response_body = handle_rpc(&body, cache);
+    let header = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap();
+    let _ = request.respond(Response::from_string(response_body).with_header(header));
+}
+
+fn handle_rpc(body: &str, cache: &Mutex<Cache>) -> String {
+    let req: RpcRequest = match serde_json::from_str(body) {
+        Ok(r) => r,
+        Err(e) => {
+            return rpc_error_json(Value::Null, -32700, format!("parse error: {}", e));
+        }
+    };
+
+    let outcome = match req.method.as_str() {
+        "get_price" => get_price(req.params, cache),
+        other => Err(RpcError {
+            code: -32601,
+            message: format!("method not found: {}", other),
+        }),
+    };
+
+    let response = match outcome {
+        Ok(result) => RpcResponse {
+            jsonrpc: "2.0",
+            result: Some(result),
+            error: None,
+            id: req.id,
+        },
+        Err(error) => RpcResponse {
+            jsonrpc: "2.0",
+            result: None,
+            error: Some(error),
+            id: req.id,
+        },
+    };
+
+    serde_json::to_string(&response).unwrap_or_else(|_| rpc_error_json(Value::Null, -32603, "internal error".into()))
+}
+
+fn rpc_error_json(id: Value, code: i32, message: String) -> String {
+    let response = RpcResponse {
+        jsonrpc: "2.0",
+        result: None,
+        error: Some(RpcError { code, message }),
+        id,
+    };
+    serde_json::to_string(&response).unwrap()
+}
+
+fn get_price(params: Value, cache: &Mutex<Cache>) -> Result<Value, RpcError> {
+    let params: GetPriceParams = serde_json::from_value(params).map_err(|e| RpcError {
+        code: -32602,
+        message: format!("invalid params: {}", e),
+    })?;
+
+    let query = PriceQuery::new(&params.coin, &params.vs_currency);
+    let mut cache = cache.lock().map_err(|_| RpcError {
+        code: -32000,
+        message: "cache lock poisoned".into(),
+    })?;
+
+    let (prices, source) = sources::fetch_prices(&query, &mut cache).map_err(|e| RpcError {
+        code: -32000,
+        message: e.to_string(),
+    })?;
+
+    let price = prices
+        .get(&query.ids[0])
+        .and_then(|by_currency| by_currency.get(&query.vs_currencies[0]))
+        .copied()
+        .ok_or_else(|| RpcError {
+            code: -32001,
+            message: "price not found".into(),
+        })?;
+
+    Ok(serde_json::json!({
+        "coin": query.ids[0],
+        "vs_currency": query.vs_currencies[0],
+        "price": price,
+        "source": source,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_method_returns_method_not_found() {
+        let cache = Mutex::new(Cache::disabled());
+        let body = r#"{"jsonrpc":"2.0","method":"not_a_real_method","params":{},"id":1}"#;
+        let response = handle_rpc(body, &cache);
+        assert!(response.contains("\"code\":-32601"));
+    }
+
+    #[test]
+    fn test_malformed_json_returns_parse_error() {
+        let cache = Mutex::new(Cache::disabled());
+        let response = handle_rpc("not json", &cache);
+        assert!(response.contains("\"code\":-32700"));
+    }
+
+    #[test]
+    fn test_get_price_with_missing_params_is_invalid_params() {
+        let cache = Mutex::new(Cache::disabled());
+        let body = r#"{"jsonrpc":"2.0","method":"get_price","params":{},"id":1}"#;
+        let response = handle_rpc(body, &cache);
+        assert!(response.contains("\"code\":-32602"));
+    }
+
+    /// Boots the daemon on an OS-assigned ephemeral port and checks over a
+    /// real socket that an unknown method gets a method-not-found error
+    /// carrying the request's id.
+    #[test]
+    fn test_serve_one_over_ephemeral_port() {
+        let server = Server::http("127.0.0.1:0").expect("bind ephemeral port");
+        let addr = server.server_addr().to_string();
+        let cache = Mutex::new(Cache::disabled());
+
+        let handle = std::thread::spawn(move || {
+            serve_one(&server, &cache).expect("serve_one");
+        });
+
+        let client = reqwest::blocking::Client::new();
+        let resp = client
+            .post(format!("http://{}", addr))
+            .body(r#"{"jsonrpc":"2.0","method":"not_a_real_method","params":{},"id":7}"#)
+            .send()
+            .expect("send rpc request");
+
+        let text = resp.text().expect("read response body");
+        assert!(text.contains("\"code\":-32601"));
+        assert!(text.contains("\"id\":7"));
+
+        handle.join().expect("server thread");
+    }
+
+    /// Exercises the `get_price` path end to end - param parsing, the
+    /// fetch/fallback call, and result shaping - by pre-seeding the shared
+    /// cache so the real handler answers from it instead of needing a live
+    /// network call to an upstream price API.
+    #[test]
+    fn test_get_price_answers_from_cache_end_to_end() {
+        let server = Server::http("127.0.0.1:0").expect("bind ephemeral port");
+        let addr = server.server_addr().to_string();
+
+        let mut cache = Cache::load(Duration::from_secs(60));
+        cache.put("solana", "usd", "CoinGecko", 123.45);
+        let cache = Mutex::new(cache);
+
+        let handle = std::thread::spawn(move || {
+            serve_one(&server, &cache).expect("serve_one");
+        });
+
+        let client = reqwest::blocking::Client::new();
+        let resp = client
+            .post(format!("http://{}", addr))
+            .body(r#"{"jsonrpc":"2.0","method":"get_price","params":{"coin":"solana","vs_currency":"usd"},"id":9}"#)
+            .send()
+            .expect("send rpc request");
+
+        let value: Value = serde_json::from_str(&resp.text().expect("read response body"))
+            .expect("parse rpc response");
+        assert_eq!(value["id"], 9);
+        assert_eq!(value["result"]["coin"], "solana");
+        assert_eq!(value["result"]["vs_currency"], "usd");
+        assert_eq!(value["result"]["price"], 123.45);
+        assert_eq!(value["result"]["source"], "CoinGecko");
+
+        handle.join().expect("server thread");
+    }
+}
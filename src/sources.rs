@@ -0,0 +1,385 @@
+//! Price sources: one `fetch_from_*` per provider, plus a fallback chain
+//! that tries them in order until one succeeds.
+
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::env;
+use std::time::Duration;
+
+use crate::cache::Cache;
+use crate::retry::{self, FetchError};
+
+/// Build a [`FetchError`] from a non-success response, capturing the
+/// `Retry-After` header (seconds or an HTTP-date are both handled by just
+/// falling back to backoff when it doesn't parse as seconds) if present.
+fn status_error(provider: &str, resp: &reqwest::blocking::Response) -> FetchError {
+    let retry_after = resp
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok())
+        .map(Duration::from_secs);
+
+    FetchError {
+        message: format!("{} API error: {}", provider, resp.status()),
+        status: Some(resp.status()),
+        retry_after,
+    }
+}
+
+/// A requested set of coins and the currencies to price them in.
+#[derive(Debug, Clone)]
+pub struct PriceQuery {
+    pub ids: Vec<String>,
+    pub vs_currencies: Vec<String>,
+}
+
+impl PriceQuery {
+    pub fn new(coins: &str, vs: &str) -> Self {
+        PriceQuery {
+            ids: coins.split(',').map(|s| s.trim().to_lowercase()).collect(),
+            vs_currencies: vs.split(',').map(|s| s.trim().to_lowercase()).collect(),
+        }
+    }
+}
+
+/// A coin/currency price map, as returned by every `fetch_from_*` function.
+pub type PriceMap = HashMap<String, HashMap<String, f64>>;
+
+/// Result type returned by every `fetch_from_*` function.
+pub type FetchResult = Result<PriceMap, Box<dyn std::error::Error>>;
+
+/// Function pointer signature shared by every price source.
+type SourceFn = fn(&reqwest::blocking::Client, &PriceQuery) -> FetchResult;
+
+#[derive(Deserialize, Debug)]
+struct CoinCapResponse {
+    data: CoinCapAsset,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct CoinCapAsset {
+    price_usd: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct BinancePrice {
+    price: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct CoinMarketCapResponse {
+    data: HashMap<String, CoinMarketCapEntry>,
+}
+
+#[derive(Deserialize, Debug)]
+struct CoinMarketCapEntry {
+    quote: HashMap<String, CoinMarketCapQuote>,
+}
+
+#[derive(Deserialize, Debug)]
+struct CoinMarketCapQuote {
+    price: f64,
+}
+
+/// CoinCap asset id for a given CoinGecko-style coin id.
+///
+/// CoinCap happens to use the same slugs as CoinGecko for the coins we
+/// care about, but keeping this as an explicit table lets us diverge
+/// without touching call sites.
+fn coincap_asset_id(coin: &str) -> &str {
+    match coin {
+        "bitcoin" => "bitcoin",
+        "ethereum" => "ethereum",
+        "solana" => "solana",
+        other => other,
+    }
+}
+
+/// Binance trading pair symbol for a given coin id, quoted in USDT.
+pub(crate) fn binance_symbol(coin: &str) -> Option<&'static str> {
+    match coin {
+        "bitcoin" => Some("BTCUSDT"),
+        "ethereum" => Some("ETHUSDT"),
+        "solana" => Some("SOLUSDT"),
+        _ => None,
+    }
+}
+
+/// CoinMarketCap ticker symbol for a given coin id.
+fn coinmarketcap_symbol(coin: &str) -> Option<&'static str> {
+    match coin {
+        "bitcoin" => Some("BTC"),
+        "ethereum" => Some("ETH"),
+        "solana" => Some("SOL"),
+        _ => None,
+    }
+}
+
+/// Fetch prices from CoinGecko for every requested coin/currency pair
+pub fn fetch_from_coingecko(
+    client: &reqwest::blocking::Client,
+    query: &PriceQuery,
+) -> FetchResult {
+    let mut url = format!(
+        "https://api.coingecko.com/api/v3/simple/price?ids={}&vs_currencies={}",
+        query.ids.join(","),
+        query.vs_currencies.join(",")
+    );
+
+    // Support API key if provided
+    if let Ok(api_key) = env::var("COINGECKO_API_KEY") {
+        url = format!("{}&x_cg_demo_api_key={}", url, api_key);
+    }
+
+    let resp = client.get(&url).send()?;
+    if !resp.status().is_success() {
+        return Err(Box::new(status_error("CoinGecko", &resp)));
+    }
+    let text = resp.text()?;
+
+    let data: PriceMap = serde_json::from_str(&text)?;
+    if data.is_empty() {
+        return Err("Price not found".into());
+    }
+    Ok(data)
+}
+
+/// Fetch prices from CoinMarketCap, which authenticates via an API key header
+/// rather than a query parameter
+pub fn fetch_from_coinmarketcap(
+    client: &reqwest::blocking::Client,
+    query: &PriceQuery,
+) -> FetchResult {
+    let api_key = env::var("COINMARKETCAP_API_KEY")
+        .map_err(|_| "COINMARKETCAP_API_KEY not set")?;
+
+    let symbols: Vec<&str> = query
+        .ids
+        .iter()
+        .map(|coin| coinmarketcap_symbol(coin).ok_or_else(|| format!("CoinMarketCap: no symbol mapping for {}", coin)))
+        .collect::<Result<_, _>>()?;
+
+    let url = format!(
+        "https://pro-api.coinmarketcap.com/v1/cryptocurrency/quotes/latest?symbol={}&convert={}",
+        symbols.join(","),
+        query.vs_currencies.join(",")
+    );
+
+    let resp = client
+        .get(&url)
+        .header("X-CMC_PRO_API_KEY", api_key)
+        .send()?;
+    if !resp.status().is_success() {
+        return Err(Box::new(status_error("CoinMarketCap", &resp)));
+    }
+    let text = resp.text()?;
+
+    let data: CoinMarketCapResponse = serde_json::from_str(&text)?;
+    let mut result = HashMap::new();
+    for coin in &query.ids {
+        let symbol = coinmarketcap_symbol(coin).ok_or_else(|| format!("CoinMarketCap: no symbol mapping for {}", coin))?;
+        let entry = data.data.get(symbol).ok_or_else(|| format!("CoinMarketCap: no data for {}", symbol))?;
+        let mut by_currency = HashMap::new();
+        for currency in &query.vs_currencies {
+            let quote = entry
+                .quote
+                .get(&currency.to_uppercase())
+                .ok_or_else(|| format!("CoinMarketCap: no {} quote for {}", currency, symbol))?;
+            by_currency.insert(currency.clone(), quote.price);
+        }
+        result.insert(coin.clone(), by_currency);
+    }
+
+    Ok(result)
+}
+
+/// Fetch prices from CoinCap, one request per coin (CoinCap has no batch
+/// endpoint). CoinCap only ever quotes in USD, so this source is skipped
+/// unless `usd` is among the requested currencies - it must never be used
+/// to answer a request for, say, `eur`.
+pub fn fetch_from_coincap(client: &reqwest::blocking::Client, query: &PriceQuery) -> FetchResult {
+    if !query.vs_currencies.iter().any(|c| c == "usd") {
+        return Err("CoinCap only quotes in usd".into());
+    }
+
+    let mut result = HashMap::new();
+
+    for coin in &query.ids {
+        let url = format!(
+            "https://api.coincap.io/v2/assets/{}",
+            coincap_asset_id(coin)
+        );
+        let resp = client.get(&url).send()?;
+        if !resp.status().is_success() {
+            return Err(Box::new(status_error("CoinCap", &resp)));
+        }
+        let text = resp.text()?;
+
+        let data: CoinCapResponse = serde_json::from_str(&text)?;
+        let price = data.data.price_usd.parse::<f64>()?;
+        result.insert(coin.clone(), HashMap::from([("usd".to_string(), price)]));
+    }
+
+    Ok(result)
+}
+
+/// Fetch prices from Binance, one request per coin (Binance has no batch
+/// endpoint). Binance quotes every pair in USDT, which we treat as USD for
+/// this purpose - like CoinCap, it's skipped unless `usd` was requested.
+pub fn fetch_from_binance(client: &reqwest::blocking::Client, query: &PriceQuery) -> FetchResult {
+    if !query.vs_currencies.iter().any(|c| c == "usd") {
+        return Err("Binance only quotes in usdt".into());
+    }
+
+    let mut result = HashMap::new();
+
+    for coin in &query.ids {
+        let symbol = binance_symbol(coin).ok_or_else(|| format!("Binance: no symbol mapping for {}", coin))?;
+        let url = format!("https://api.binance.com/api/v3/ticker/price?symbol={}", symbol);
+        let resp = client.get(&url).send()?;
+        if !resp.status().is_success() {
+            return Err(Box::new(status_error("Binance", &resp)));
+        }
+        let text = resp.text()?;
+
+        let data: BinancePrice = serde_json::from_str(&text)?;
+        let price = data.price.parse::<f64>()?;
+        result.insert(coin.clone(), HashMap::from([("usd".to_string(), price)]));
+    }
+
+    Ok(result)
+}
+
+/// One source's name paired with the function to query it.
+pub const SOURCES: &[(&str, SourceFn)] = &[
+    ("CoinGecko", fetch_from_coingecko),
+    ("CoinMarketCap", fetch_from_coinmarketcap),
+    ("CoinCap", fetch_from_coincap),
+    ("Binance", fetch_from_binance),
+];
+
+pub fn new_client() -> Result<reqwest::blocking::Client, Box<dyn std::error::Error>> {
+    Ok(reqwest::blocking::Client::builder()
+        .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36")
+        .timeout(std::time::Duration::from_secs(10))
+        .build()?)
+}
+
+/// Read every (coin, currency) the query asks for out of the cache for
+/// `source`, returning `None` unless all of them are present and fresh.
+fn cached_prices(cache: &Cache, source: &str, query: &PriceQuery) -> Option<PriceMap> {
+    let mut result = PriceMap::new();
+    for coin in &query.ids {
+        let mut by_currency = HashMap::new();
+        for currency in &query.vs_currencies {
+            by_currency.insert(currency.clone(), cache.get(coin, currency, source)?);
+        }
+        result.insert(coin.clone(), by_currency);
+    }
+    Some(result)
+}
+
+fn store_prices(cache: &mut Cache, source: &str, prices: &PriceMap) {
+    for (coin, by_currency) in prices {
+        for (currency, price) in by_currency {
+            cache.put(coin, currency, source, *price);
+        }
+    }
+}
+
+/// Try fetching prices for the given query from multiple sources with
+/// fallback, retrying rate-limited/unavailable sources before moving on,
+/// and short-circuiting through `cache` when a fresh entry already exists.
+/// Returns the prices alongside the name of the source that answered.
+pub fn fetch_prices(
+    query: &PriceQuery,
+    cache: &mut Cache,
+) -> Result<(PriceMap, &'static str), Box<dyn std::error::Error>> {
+    let client = new_client()?;
+
+    for (name, fetch) in SOURCES {
+        if let Some(prices) = cached_prices(cache, name, query) {
+            println!("🗄️  {}: cached", name);
+            return Ok((prices, name));
+        }
+
+        println!("🔗 Trying {} API...", name);
+        match retry::with_retry(name, || fetch(&client, query)) {
+            Ok(prices) => {
+                println!("✅ {}: Success", name);
+                store_prices(cache, name, &prices);
+                return Ok((prices, name));
+            }
+            Err(e) => {
+                eprintln!("⚠️  {} failed: {}", name, e);
+            }
+        }
+    }
+
+    Err("❌ All API sources failed. Please check your internet connection or try again later.".into())
+}
+
+/// Query every source and return each one's result, tagged by source name,
+/// instead of stopping at the first success. Used by aggregate mode to build
+/// a consensus price across providers.
+pub fn fetch_from_all_sources(
+    query: &PriceQuery,
+    cache: &mut Cache,
+) -> Result<Vec<(&'static str, FetchResult)>, Box<dyn std::error::Error>> {
+    let client = new_client()?;
+
+    Ok(SOURCES
+        .iter()
+        .map(|(name, fetch)| {
+            if let Some(prices) = cached_prices(cache, name, query) {
+                return (*name, Ok(prices));
+            }
+
+            let result = retry::with_retry(name, || fetch(&client, query));
+            if let Ok(prices) = &result {
+                store_prices(cache, name, prices);
+            }
+            (*name, result)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_price_query_parses_comma_separated_lists() {
+        let query = PriceQuery::new("Bitcoin, ethereum ,SOLANA", "USD,eur");
+        assert_eq!(query.ids, vec!["bitcoin", "ethereum", "solana"]);
+        assert_eq!(query.vs_currencies, vec!["usd", "eur"]);
+    }
+
+    #[test]
+    fn test_binance_symbol_mapping() {
+        assert_eq!(binance_symbol("solana"), Some("SOLUSDT"));
+        assert_eq!(binance_symbol("not-a-real-coin"), None);
+    }
+
+    #[test]
+    fn test_coinmarketcap_symbol_mapping() {
+        assert_eq!(coinmarketcap_symbol("solana"), Some("SOL"));
+        assert_eq!(coinmarketcap_symbol("not-a-real-coin"), None);
+    }
+
+    #[test]
+    fn test_coincap_skips_non_usd_queries_without_a_network_call() {
+        let client = reqwest::blocking::Client::new();
+        let query = PriceQuery::new("solana", "eur");
+        assert!(fetch_from_coincap(&client, &query).is_err());
+    }
+
+    #[test]
+    fn test_binance_skips_non_usd_queries_without_a_network_call() {
+        let client = reqwest::blocking::Client::new();
+        let query = PriceQuery::new("solana", "eur");
+        assert!(fetch_from_binance(&client, &query).is_err());
+    }
+}
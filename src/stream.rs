@@ -0,0 +1,128 @@
+//! `watch`: live price updates pushed from Binance's WebSocket ticker
+//! stream, instead of polling the REST endpoint.
+
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tungstenite::connect;
+
+use crate::sources::binance_symbol;
+
+const BASE_URL: &str = "wss://stream.binance.com:9443/stream";
+/// Cap on reconnect backoff so a long outage doesn't leave us waiting
+/// minutes between attempts.
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+#[derive(Deserialize)]
+struct CombinedMessage {
+    data: Ticker,
+}
+
+#[derive(Deserialize)]
+struct Ticker {
+    /// Trading pair symbol, e.g. "SOLUSDT"
+    s: String,
+    /// Last price
+    c: String,
+}
+
+fn reconnect_delay(attempt: u32) -> Duration {
+    let backoff = Duration::from_secs(1).saturating_mul(1 << attempt.min(5));
+    backoff.min(MAX_BACKOFF)
+}
+
+fn stream_url(coins: &[String]) -> Result<String, Box<dyn std::error::Error>> {
+    let streams: Vec<String> = coins
+        .iter()
+        .map(|coin| {
+            binance_symbol(coin)
+                .map(|symbol| format!("{}@ticker", symbol.to_lowercase()))
+                .ok_or_else(|| format!("Binance: no symbol mapping for {}", coin))
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(format!("{}?streams={}", BASE_URL, streams.join("/")))
+}
+
+/// Subscribe to Binance's ticker stream for `coins` and print updates as
+/// they arrive, reconnecting with backoff on disconnect. Updates for a
+/// given coin are throttled to at most one per `interval`.
+pub fn run(coins: &str, interval: Duration) -> Result<(), Box<dyn std::error::Error>> {
+    let ids: Vec<String> = coins.split(',').map(|s| s.trim().to_lowercase()).collect();
+    let url = stream_url(&ids)?;
+
+    let mut last_printed: HashMap<String, Instant> = HashMap::new();
+    let mut attempt = 0;
+
+    loop {
+        println!("🔌 Connecting to Binance ticker stream for {}...", ids.join(", "));
+        match connect(&url) {
+            Ok((mut socket, _response)) => {
+                attempt = 0;
+                println!("✅ Connected, watching for updates (ctrl-c to stop)");
+
+                loop {
+                    let msg = match socket.read() {
+                        Ok(msg) => msg,
+                        Err(e) => {
+                            eprintln!("⚠️  WebSocket read failed: {}", e);
+                            break;
+                        }
+                    };
+
+                    let tungstenite::Message::Text(text) = msg else {
+                        continue;
+                    };
+
+                    let Ok(parsed) = serde_json::from_str::<CombinedMessage>(&text) else {
+                        continue;
+                    };
+
+                    let should_print = last_printed
+                        .get(&parsed.data.s)
+                        .map(|last| last.elapsed() >= interval)
+                        .unwrap_or(true);
+
+                    if should_print {
+                        println!("💰 {}: {}", parsed.data.s, parsed.data.c);
+                        last_printed.insert(parsed.data.s.clone(), Instant::now());
+                    }
+                }
+            }
+            Err(e) => {
+                eprintln!("⚠️  Failed to connect: {}", e);
+            }
+        }
+
+        let delay = reconnect_delay(attempt);
+        eprintln!("🔁 Reconnecting in {:.0}s...", delay.as_secs_f64());
+        std::thread::sleep(delay);
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stream_url_builds_combined_stream_path() {
+        let url = stream_url(&["solana".to_string(), "bitcoin".to_string()]).unwrap();
+        assert_eq!(
+            url,
+            "wss://stream.binance.com:9443/stream?streams=solusdt@ticker/btcusdt@ticker"
+        );
+    }
+
+    #[test]
+    fn test_stream_url_rejects_unmapped_coin() {
+        assert!(stream_url(&["not-a-real-coin".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_reconnect_delay_caps_at_max_backoff() {
+        assert_eq!(reconnect_delay(0), Duration::from_secs(1));
+        assert_eq!(reconnect_delay(2), Duration::from_secs(4));
+        assert_eq!(reconnect_delay(10), MAX_BACKOFF);
+    }
+}